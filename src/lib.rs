@@ -23,6 +23,19 @@
 //!    should dispose-of properly.
 //!  - have a [`dup()`](trait.ManagedFD.html#tymethod.dup) method that clones handle accordingly,
 //!    returning eventual errors.
+//!  - have [`dup_wrap_cloexec(fd)`](trait.ManagedFD.html#tymethod.dup_wrap_cloexec) and
+//!    [`dup_cloexec()`](trait.ManagedFD.html#tymethod.dup_cloexec) counterparts of the above that
+//!    atomically set `FD_CLOEXEC` on the new handle, so it isn't leaked across `fork()`/`exec()`.
+//!  - implement `FromRawFd`, `IntoRawFd` and `AsFd` and have a
+//!    [`try_clone_to_owned()`](trait.ManagedFD.html#method.try_clone_to_owned) method, so they
+//!    interoperate with std's io-safety types (`OwnedFd`/`BorrowedFd`). `SharedFD` exposes a
+//!    fallible [`try_into_raw_fd()`](struct.SharedFD.html#method.try_into_raw_fd) instead of
+//!    `IntoRawFd`, since giving up its shared handle can only succeed when no other clone holds it.
+//!  - have a [`pipe()`](trait.ManagedFD.html#method.pipe) constructor that creates an OS pipe and
+//!    returns both ends already wrapped and close-on-exec.
+//!  - have a [`redirect_to(target)`](trait.ManagedFD.html#method.redirect_to) method that
+//!    atomically installs the handle onto a caller-chosen fd number, e.g. to land a managed
+//!    descriptor on stdin/stdout before `exec()`.
 //!
 //! # Multi-access
 //! Both are **not** multi-access safe, with `SharedFD` being even less safe.
@@ -31,8 +44,37 @@
 //!   each other's toes during writes)
 //! - All the related `SharedFD` instances have a _single, shared_ read/write pointer.
 //!
+//! # Runtime-selectable sharing
+//! [`AnyManagedFD`](enum.AnyManagedFD.html) type-erases `DuplicatingFD`/`SharedFD` behind one
+//! concrete type, picking between them via a [`SharingStrategy`](enum.SharingStrategy.html)
+//! chosen at runtime - useful when the duplication policy for a stored descriptor should be
+//! configurable instead of baked into the type.
+//!
+//! # Platform support
+//! The documentation above describes the `#[cfg(unix)]` build, backed by `RawFd`,
+//! `dup(2)`/`close(2)`. Under `#[cfg(windows)]`, `DuplicatingFD` and `SharedFD` are instead
+//! backed by `RawHandle`, duplicating via `DuplicateHandle` and closing via `CloseHandle`, with
+//! `AsRawHandle`/`AsRawSocket` standing in for `AsRawFd`. The two builds are mutually exclusive,
+//! so `ManagedFD`, `DuplicatingFD` and `SharedFD` exist under the same names on either platform -
+//! but the Windows `ManagedFD` only covers `wrap`/`dup_wrap`/`dup`. The close-on-exec variants
+//! (`dup_wrap_cloexec`/`dup_cloexec`), the io-safety bridge (`FromRawFd`/`IntoRawFd`/`AsFd`/
+//! `try_clone_to_owned`), `pipe()`, `redirect_to()` and `AnyManagedFD`/`SharingStrategy` are all
+//! Unix-only for now and have no Windows equivalent yet.
+//!
 use std::io;
+#[cfg(unix)]
+use std::os::unix::io::AsFd;
+#[cfg(unix)]
 use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::os::unix::io::BorrowedFd;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::io::IntoRawFd;
+#[cfg(unix)]
+use std::os::unix::io::OwnedFd;
+#[cfg(unix)]
 use std::os::unix::io::RawFd;
 use std::sync::Arc;
 
@@ -40,6 +82,7 @@ use std::sync::Arc;
 /// auto-closing on drop and performing sensible `clone()`/`dup()` operations.
 ///
 /// Warning: `Clone` trait has no way to convey errors, so implementations are forced to `panic!()`.
+#[cfg(unix)]
 pub trait ManagedFD
 where
     Self: AsRawFd + Clone,
@@ -64,11 +107,232 @@ where
     /// }
     /// ```
     fn dup(&self) -> io::Result<Self>;
+
+    /// Wrap a close-on-exec [dup(2)](https://man7.org/linux/man-pages/man2/dup.2.html) copy of
+    /// `fd` in `ManagedFD`. Like [`dup_wrap`](#tymethod.dup_wrap), it doesn't take ownership of
+    /// the original `fd`, but the new handle already has `FD_CLOEXEC` set, so it won't leak into
+    /// a child process across `fork()`/`exec()`.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let h = DuplicatingFD::dup_wrap_cloexec(stdout_handle).unwrap();
+    ///
+    /// let flags = unsafe { libc::fcntl(h.as_raw_fd(), libc::F_GETFD) };
+    /// assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    /// ```
+    fn dup_wrap_cloexec(fd: RawFd) -> io::Result<Self>;
+
+    /// Create a close-on-exec duplicate of handle, following the same instance-lifetime rules as
+    /// [`dup`](#tymethod.dup), but with `FD_CLOEXEC` set on the new handle.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let h = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let h2 = h.dup_cloexec().unwrap();
+    ///
+    /// let flags = unsafe { libc::fcntl(h2.as_raw_fd(), libc::F_GETFD) };
+    /// assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    /// assert_ne!(h2.as_raw_fd(), h.as_raw_fd());
+    /// ```
+    fn dup_cloexec(&self) -> io::Result<Self>;
+
+    /// Duplicate the handle into a std [`OwnedFd`], close-on-exec, for handing off to APIs that
+    /// speak the io-safety types instead of this crate's `ManagedFD`. The original handle is left
+    /// untouched and still managed by `self`.
+    ///
+    /// Uses the same atomic `fcntl(F_DUPFD_CLOEXEC)` (falling back to `dup()` +
+    /// `fcntl(F_SETFD)`) as [`dup_cloexec`](#tymethod.dup_cloexec) - handing a descriptor to
+    /// code that doesn't know about `ManagedFD` is the highest-risk path for leaking it across
+    /// `fork()`/`exec()`, so it shouldn't default to leaving `FD_CLOEXEC` unset.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let h = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let owned = h.try_clone_to_owned().unwrap();
+    ///
+    /// assert_ne!(owned.as_raw_fd(), stdout_handle);
+    /// assert_ne!(owned.as_raw_fd(), h.as_raw_fd());
+    ///
+    /// let flags = unsafe { libc::fcntl(owned.as_raw_fd(), libc::F_GETFD) };
+    /// assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    /// ```
+    fn try_clone_to_owned(&self) -> io::Result<OwnedFd> {
+        Ok(unsafe { OwnedFd::from_raw_fd(AutoClosingFD::raw_dup_cloexec(self.as_raw_fd())?) })
+    }
+
+    /// Create an OS pipe and return its `(read_end, write_end)` already wrapped in `ManagedFD`,
+    /// with `FD_CLOEXEC` set on both ends. Since each end is a regular managed handle, it is
+    /// auto-closed on drop and can be cheaply [`dup()`](#tymethod.dup)-ed to pass into multiple
+    /// children - exactly the ownership model the rest of this crate provides for descriptors.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::fs::File;
+    /// use std::io::{Read, Write};
+    /// use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+    ///
+    /// let (read_end, write_end) = DuplicatingFD::pipe().unwrap();
+    ///
+    /// // Both ends are close-on-exec.
+    /// let flags = unsafe { libc::fcntl(read_end.as_raw_fd(), libc::F_GETFD) };
+    /// assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    ///
+    /// let mut writer = unsafe { File::from_raw_fd(write_end.into_raw_fd()) };
+    /// let mut reader = unsafe { File::from_raw_fd(read_end.into_raw_fd()) };
+    ///
+    /// writer.write_all(b"hi").unwrap();
+    /// drop(writer);
+    ///
+    /// let mut buf = String::new();
+    /// reader.read_to_string(&mut buf).unwrap();
+    /// assert_eq!(buf, "hi");
+    /// ```
+    fn pipe() -> io::Result<(Self, Self)> {
+        let (read_fd, write_fd) = raw_pipe_cloexec()?;
+        Ok((Self::wrap(read_fd), Self::wrap(write_fd)))
+    }
+
+    /// Atomically install this handle onto descriptor number `target`, e.g. to make a managed
+    /// descriptor become the new stdin/stdout before `exec()`. Closes whatever was previously
+    /// open at `target` as part of the same syscall, and leaves `target` inheritable across
+    /// `exec()` (`FD_CLOEXEC` cleared) - the usual requirement for redirected standard streams.
+    ///
+    /// Prefers [dup3(2)](https://man7.org/linux/man-pages/man2/dup3.2.html) with no flags, which
+    /// behaves like [dup2(2)](https://man7.org/linux/man-pages/man2/dup2.2.html) but - unlike it -
+    /// fails instead of silently doing nothing when `target` already refers to this handle.
+    /// Platforms without `dup3(2)` fall back to `dup2(2)`.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let source = DuplicatingFD::dup_wrap_cloexec(stdout_handle).unwrap();
+    /// let target = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let target_fd = target.as_raw_fd();
+    ///
+    /// source.redirect_to(target_fd).unwrap();
+    ///
+    /// // CLOEXEC has been cleared on `target_fd`, even though `source` had it set.
+    /// let flags = unsafe { libc::fcntl(target_fd, libc::F_GETFD) };
+    /// assert_eq!(flags & libc::FD_CLOEXEC, 0);
+    /// ```
+    fn redirect_to(&self, target: RawFd) -> io::Result<()> {
+        raw_redirect_to(self.as_raw_fd(), target)
+    }
+}
+
+/// Platforms with "modern" `dup3(2)`/`pipe2(2)` syscalls that atomically set flags in the same
+/// call that creates the new descriptor, as opposed to an older call plus a separate `fcntl`.
+/// Kept as a single macro so [`raw_redirect_to`] and [`raw_pipe_cloexec`] can't drift apart if a
+/// platform is added to one list and forgotten in the other.
+#[cfg(unix)]
+macro_rules! with_modern_dup_pipe_syscalls {
+    ($modern:block else $fallback:block) => {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "dragonfly",
+        ))]
+        $modern
+
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "dragonfly",
+        )))]
+        $fallback
+    };
+}
+
+/// Atomically duplicate `fd` onto descriptor number `target`, closing whatever was previously
+/// open there. See [`ManagedFD::redirect_to`](trait.ManagedFD.html#method.redirect_to).
+#[cfg(unix)]
+fn raw_redirect_to(fd: RawFd, target: RawFd) -> io::Result<()> {
+    with_modern_dup_pipe_syscalls! {
+        {
+            let rc = unsafe { libc::dup3(fd, target, 0) };
+            if rc == -1 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        } else {
+            let rc = unsafe { libc::dup2(fd, target) };
+            if rc == -1 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Create an OS pipe, returning its `(read_fd, write_fd)` raw descriptors, both already marked
+/// close-on-exec.
+///
+/// Prefers [pipe2(2)](https://man7.org/linux/man-pages/man2/pipe2.2.html) with `O_CLOEXEC`, which
+/// creates both ends with the flag already set, avoiding the fork-race of setting it afterwards.
+/// Platforms without `pipe2(2)` fall back to [pipe(2)](https://man7.org/linux/man-pages/man2/pipe.2.html)
+/// followed by a `fcntl(F_SETFD, FD_CLOEXEC)` on each end, which reopens that race briefly.
+#[cfg(unix)]
+fn raw_pipe_cloexec() -> io::Result<(RawFd, RawFd)> {
+    with_modern_dup_pipe_syscalls! {
+        {
+            let mut fds: [RawFd; 2] = [-1, -1];
+            let rc = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) };
+            if rc == -1 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok((fds[0], fds[1]))
+            }
+        } else {
+            let mut fds: [RawFd; 2] = [-1, -1];
+            let rc = unsafe { libc::pipe(fds.as_mut_ptr()) };
+            if rc == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            for &fd in &fds {
+                let rc = unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+                if rc == -1 {
+                    let err = io::Error::last_os_error();
+                    unsafe {
+                        libc::close(fds[0]);
+                        libc::close(fds[1]);
+                    }
+                    return Err(err);
+                }
+            }
+            Ok((fds[0], fds[1]))
+        }
+    }
 }
 
 /// Intermediate auto-closing handle. Does not implement `clone()`, but can create itself off a
 /// `dup(2)` clone.
+#[cfg(unix)]
 struct AutoClosingFD(RawFd);
+#[cfg(unix)]
 impl AutoClosingFD{
     #[inline]
     fn wrap(fd: RawFd) -> Self {
@@ -84,8 +348,80 @@ impl AutoClosingFD{
             Ok(Self::wrap(new_handle))
         }
     }
+
+    #[inline]
+    fn dup_wrap_cloexec(fd: RawFd) -> io::Result<Self> {
+        Ok(Self::wrap(Self::raw_dup_cloexec(fd)?))
+    }
+
+    #[inline]
+    fn dup_cloexec(&self) -> io::Result<Self> {
+        Ok(Self::wrap(Self::raw_dup_cloexec(self.0)?))
+    }
+
+    /// Atomically duplicate `fd`, returning a new handle with `FD_CLOEXEC` already set.
+    ///
+    /// Prefers `fcntl(F_DUPFD_CLOEXEC)`, which creates the new descriptor and sets the flag in a
+    /// single syscall, closing the window where another thread could `fork()` between a plain
+    /// `dup()` and a following `fcntl(F_SETFD, FD_CLOEXEC)`. On the rare platform lacking
+    /// `F_DUPFD_CLOEXEC` this falls back to that two-step sequence, which reopens the race for
+    /// the brief interval between the two calls.
+    fn raw_dup_cloexec(fd: RawFd) -> io::Result<RawFd> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "dragonfly",
+        ))]
+        {
+            let new_handle = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+            if new_handle == -1 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(new_handle)
+            }
+        }
+
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "dragonfly",
+        )))]
+        {
+            let new_handle = unsafe { libc::dup(fd) };
+            if new_handle == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            let rc = unsafe { libc::fcntl(new_handle, libc::F_SETFD, libc::FD_CLOEXEC) };
+            if rc == -1 {
+                let err = io::Error::last_os_error();
+                unsafe { libc::close(new_handle) };
+                return Err(err);
+            }
+            Ok(new_handle)
+        }
+    }
+
+    /// Extract the raw handle, suppressing the auto-close this instance would otherwise perform.
+    /// The caller becomes responsible for the handle's lifetime.
+    #[inline]
+    fn take_raw_fd(mut self) -> RawFd {
+        let fd = self.0;
+        self.0 = -1;
+        fd
+    }
 }
 
+#[cfg(unix)]
 impl Drop for AutoClosingFD{
     fn drop(&mut self) {
         if self.0 >= 0 {
@@ -95,6 +431,7 @@ impl Drop for AutoClosingFD{
     }
 }
 
+#[cfg(unix)]
 impl  AsRawFd for AutoClosingFD {
     fn as_raw_fd(&self) -> RawFd {
         self.0
@@ -129,8 +466,10 @@ impl  AsRawFd for AutoClosingFD {
 /// assert_ne!(myOtherDupH.as_raw_fd(),myDupH.as_raw_fd());
 ///
 /// ```
+#[cfg(unix)]
 pub struct DuplicatingFD(AutoClosingFD);
 
+#[cfg(unix)]
 impl ManagedFD for DuplicatingFD {
     fn wrap(fd: RawFd) -> Self {
         DuplicatingFD(AutoClosingFD::wrap(fd))
@@ -148,27 +487,54 @@ impl ManagedFD for DuplicatingFD {
             Ok(Self::wrap(new_handle))
         }
     }
+
+    fn dup_wrap_cloexec(fd: RawFd) -> io::Result<Self> {
+        Ok(DuplicatingFD(AutoClosingFD::dup_wrap_cloexec(fd)?))
+    }
+
+    fn dup_cloexec(&self) -> io::Result<Self> {
+        Ok(DuplicatingFD(self.0.dup_cloexec()?))
+    }
 }
 
+#[cfg(unix)]
 impl Clone for DuplicatingFD {
     fn clone(&self) -> Self {
         self.dup().unwrap()
     }
 
+    /// Redirects `self`'s handle onto `source`'s via
+    /// [`redirect_to`](trait.ManagedFD.html#method.redirect_to), so `self` keeps its own fd
+    /// number but ends up referring to the same open file description as `source`.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let source = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let mut target = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let target_fd = target.as_raw_fd();
+    ///
+    /// target.clone_from(&source);
+    ///
+    /// // `target` kept its fd number, but now shares an open file description with `source`.
+    /// assert_eq!(target.as_raw_fd(), target_fd);
+    /// ```
     fn clone_from(&mut self, source: &Self) {
         assert!(source.as_raw_fd()>=0);
         assert!(self.as_raw_fd()>=0);
 
         if source.as_raw_fd() != self.as_raw_fd() {
-            unsafe { libc::close(self.as_raw_fd()) };
-            let rc = unsafe { libc::dup2(source.as_raw_fd(), self.as_raw_fd()) };
-            if rc == -1 {
-                panic!(io::Error::last_os_error());
-            }
+            source
+                .redirect_to(self.as_raw_fd())
+                .expect("redirect_to failed while cloning DuplicatingFD");
         }
     }
 }
 
+#[cfg(unix)]
 impl AsRawFd for DuplicatingFD {
     #[inline]
     fn as_raw_fd(&self) -> RawFd {
@@ -176,6 +542,50 @@ impl AsRawFd for DuplicatingFD {
     }
 }
 
+#[cfg(unix)]
+impl AsFd for DuplicatingFD {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for DuplicatingFD {
+    /// Wrap `fd`, taking ownership of it, same as [`ManagedFD::wrap`](trait.ManagedFD.html#tymethod.wrap).
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::wrap(fd)
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for DuplicatingFD {
+    /// Extract the raw handle and suppress the auto-close `Drop` would otherwise perform. The
+    /// caller takes ownership of `fd` and is responsible for closing it.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let h = DuplicatingFD::dup_wrap(stdout_handle).unwrap();
+    /// let raw = h.as_raw_fd();
+    ///
+    /// let fd = h.into_raw_fd();
+    /// assert_eq!(fd, raw);
+    ///
+    /// // Round-trip it back into a managed handle, which will close it on drop.
+    /// let h2 = unsafe { DuplicatingFD::from_raw_fd(fd) };
+    /// assert_eq!(h2.as_raw_fd(), fd);
+    /// ```
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.0.take_raw_fd()
+    }
+}
+
 /// Implements `Clone` trait that creates new `SharedFD` with `Arc::clone` of the
 /// embedded handle.
 ///
@@ -201,8 +611,10 @@ impl AsRawFd for DuplicatingFD {
 /// assert_eq!(myOtherShH.as_raw_fd(),myShH.as_raw_fd());
 ///
 /// ```
+#[cfg(unix)]
 pub struct SharedFD(Arc<AutoClosingFD>);
 
+#[cfg(unix)]
 impl ManagedFD for SharedFD {
     fn wrap(fd: RawFd) -> Self {
         SharedFD(Arc::new(AutoClosingFD::wrap(fd)))
@@ -215,14 +627,386 @@ impl ManagedFD for SharedFD {
     fn dup(&self) -> io::Result<Self> {
         Ok(SharedFD(self.0.clone()))
     }
+
+    fn dup_wrap_cloexec(fd: RawFd) -> io::Result<Self> {
+        Ok(SharedFD(Arc::new(AutoClosingFD::dup_wrap_cloexec(fd)?)))
+    }
+
+    fn dup_cloexec(&self) -> io::Result<Self> {
+        Ok(SharedFD(Arc::new(self.0.dup_cloexec()?)))
+    }
 }
 
+#[cfg(unix)]
 impl AsRawFd for SharedFD {
     fn as_raw_fd(&self) -> RawFd {
         self.0.as_raw_fd()
     }
 }
 
+#[cfg(unix)]
+impl AsFd for SharedFD {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for SharedFD {
+    /// Wrap `fd`, taking ownership of it, same as [`ManagedFD::wrap`](trait.ManagedFD.html#tymethod.wrap).
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::wrap(fd)
+    }
+}
+
+#[cfg(unix)]
+impl SharedFD {
+    /// Extract the raw handle, but only if `self` is the last `SharedFD` referencing it (i.e.
+    /// the backing `Arc`'s strong count is 1).
+    ///
+    /// Unlike `IntoRawFd`, which offers no way to signal failure, giving up a *shared* handle
+    /// while other clones still depend on it would either close a descriptor out from under them
+    /// or silently leak it, so this returns the original `SharedFD` back to the caller when other
+    /// clones are still alive.
+    ///
+    /// # Example
+    /// ```
+    /// use pakr_managedrawfd::*;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+    /// let h = SharedFD::dup_wrap(stdout_handle).unwrap();
+    /// let h2 = h.clone();
+    /// let raw = h.as_raw_fd();
+    ///
+    /// // Another clone is still alive, so this must fail and hand `h` back unchanged.
+    /// let h = match h.try_into_raw_fd() {
+    ///     Ok(_) => panic!("should still be shared"),
+    ///     Err(h) => h,
+    /// };
+    /// assert_eq!(h.as_raw_fd(), raw);
+    ///
+    /// drop(h2);
+    ///
+    /// // Now `h` is the last reference, so extraction succeeds.
+    /// let fd = match h.try_into_raw_fd() {
+    ///     Ok(fd) => fd,
+    ///     Err(_) => panic!("should no longer be shared"),
+    /// };
+    /// assert_eq!(fd, raw);
+    ///
+    /// unsafe { libc::close(fd) };
+    /// ```
+    pub fn try_into_raw_fd(self) -> Result<RawFd, Self> {
+        match Arc::try_unwrap(self.0) {
+            Ok(inner) => Ok(inner.take_raw_fd()),
+            Err(shared) => Err(SharedFD(shared)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Clone for SharedFD {
+    fn clone(&self) -> Self {
+        self.dup().unwrap()
+    }
+}
+
+/// Selects which concrete `ManagedFD` backs an [`AnyManagedFD`] - i.e. whether duplicating it
+/// gives every clone its own handle, or has all clones share one.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharingStrategy {
+    /// Each clone gets its own handle - see [`DuplicatingFD`].
+    PerClone,
+    /// All clones share one handle - see [`SharedFD`].
+    Shared,
+}
+
+/// Type-erased handle that holds either a [`DuplicatingFD`] or a [`SharedFD`] behind one concrete
+/// type, dispatching `dup()`/`Clone`/`AsRawFd` to whichever is inside.
+///
+/// This lets a library accept a runtime-chosen [`SharingStrategy`] - e.g. read from a config file
+/// or CLI flag - and decide the duplication policy for a stored descriptor without becoming
+/// generic over `ManagedFD` everywhere, while still honoring each variant's drop/close behavior.
+///
+/// # Example
+/// ```
+/// use pakr_managedrawfd::*;
+/// use std::os::unix::io::AsRawFd;
+///
+/// let stdout_handle = std::io::stdout().lock().as_raw_fd();
+///
+/// let per_clone = AnyManagedFD::dup_wrap(stdout_handle, SharingStrategy::PerClone).unwrap();
+/// let per_clone2 = per_clone.dup().unwrap();
+/// assert_ne!(per_clone.as_raw_fd(), per_clone2.as_raw_fd());
+///
+/// let shared = AnyManagedFD::dup_wrap(stdout_handle, SharingStrategy::Shared).unwrap();
+/// let shared2 = shared.dup().unwrap();
+/// assert_eq!(shared.as_raw_fd(), shared2.as_raw_fd());
+/// ```
+#[cfg(unix)]
+pub enum AnyManagedFD {
+    PerClone(DuplicatingFD),
+    Shared(SharedFD),
+}
+
+#[cfg(unix)]
+impl AnyManagedFD {
+    /// Wrap `fd` using the handle-duplication policy selected by `strategy`. See
+    /// [`ManagedFD::wrap`](trait.ManagedFD.html#tymethod.wrap).
+    pub fn wrap(fd: RawFd, strategy: SharingStrategy) -> Self {
+        match strategy {
+            SharingStrategy::PerClone => AnyManagedFD::PerClone(DuplicatingFD::wrap(fd)),
+            SharingStrategy::Shared => AnyManagedFD::Shared(SharedFD::wrap(fd)),
+        }
+    }
+
+    /// Wrap a [dup(2)](https://man7.org/linux/man-pages/man2/dup.2.html) copy of `fd` using the
+    /// policy selected by `strategy`. See
+    /// [`ManagedFD::dup_wrap`](trait.ManagedFD.html#tymethod.dup_wrap).
+    pub fn dup_wrap(fd: RawFd, strategy: SharingStrategy) -> io::Result<Self> {
+        Ok(match strategy {
+            SharingStrategy::PerClone => AnyManagedFD::PerClone(DuplicatingFD::dup_wrap(fd)?),
+            SharingStrategy::Shared => AnyManagedFD::Shared(SharedFD::dup_wrap(fd)?),
+        })
+    }
+
+    /// Create a duplicate, honoring whichever policy `self` was constructed with. See
+    /// [`ManagedFD::dup`](trait.ManagedFD.html#tymethod.dup).
+    pub fn dup(&self) -> io::Result<Self> {
+        Ok(match self {
+            AnyManagedFD::PerClone(fd) => AnyManagedFD::PerClone(fd.dup()?),
+            AnyManagedFD::Shared(fd) => AnyManagedFD::Shared(fd.dup()?),
+        })
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for AnyManagedFD {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            AnyManagedFD::PerClone(fd) => fd.as_raw_fd(),
+            AnyManagedFD::Shared(fd) => fd.as_raw_fd(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Clone for AnyManagedFD {
+    fn clone(&self) -> Self {
+        match self {
+            AnyManagedFD::PerClone(fd) => AnyManagedFD::PerClone(fd.clone()),
+            AnyManagedFD::Shared(fd) => AnyManagedFD::Shared(fd.clone()),
+        }
+    }
+}
+
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
+#[cfg(windows)]
+use std::os::windows::io::AsRawSocket;
+#[cfg(windows)]
+use std::os::windows::io::RawHandle;
+#[cfg(windows)]
+use std::os::windows::io::RawSocket;
+
+#[cfg(windows)]
+const DUPLICATE_SAME_ACCESS: u32 = 0x0000_0002;
+
+#[cfg(windows)]
+extern "system" {
+    fn CloseHandle(h_object: RawHandle) -> i32;
+    fn GetCurrentProcess() -> RawHandle;
+    #[allow(clippy::too_many_arguments)]
+    fn DuplicateHandle(
+        h_source_process_handle: RawHandle,
+        h_source_handle: RawHandle,
+        h_target_process_handle: RawHandle,
+        lp_target_handle: *mut RawHandle,
+        dw_desired_access: u32,
+        b_inherit_handle: i32,
+        dw_options: u32,
+    ) -> i32;
+}
+
+/// Trait `ManagedFD` describes a managed Windows `RawHandle`, with primary functionality of
+/// auto-closing on drop and performing sensible `clone()`/`dup()` operations.
+///
+/// This is the `#[cfg(windows)]` counterpart of the Unix `ManagedFD`, with `AsRawHandle` standing
+/// in for `AsRawFd`. It only has `wrap`/`dup_wrap`/`dup`; the Unix trait's other methods
+/// (`dup_wrap_cloexec`, `dup_cloexec`, `try_clone_to_owned`, `pipe`, `redirect_to`) are not
+/// available here.
+///
+/// Warning: `Clone` trait has no way to convey errors, so implementations are forced to `panic!()`.
+#[cfg(windows)]
+pub trait ManagedFD
+where
+    Self: AsRawHandle + Clone,
+{
+    /// Wrap `handle` in `ManagedFD`. You should not use the naked handle afterwards, in
+    /// particular *don't close it*.
+    fn wrap(handle: RawHandle) -> Self;
+
+    /// Wrap a [`DuplicateHandle`](https://learn.microsoft.com/en-us/windows/win32/api/handleapi/nf-handleapi-duplicatehandle)
+    /// copy of `handle` in `ManagedFD`. You should dispose the original `handle` properly at your
+    /// discretion.
+    fn dup_wrap(handle: RawHandle) -> io::Result<Self>;
+
+    /// Create a duplicate of handle in such a way, that dropping of one instance has no influence
+    /// on the other ones.
+    fn dup(&self) -> io::Result<Self>;
+}
+
+/// Intermediate auto-closing handle. Does not implement `clone()`, but can create itself off a
+/// `DuplicateHandle` clone.
+#[cfg(windows)]
+struct AutoClosingFD(RawHandle);
+
+#[cfg(windows)]
+impl AutoClosingFD {
+    #[inline]
+    fn wrap(handle: RawHandle) -> Self {
+        AutoClosingFD(handle)
+    }
+
+    #[inline]
+    fn dup_wrap(handle: RawHandle) -> io::Result<Self> {
+        Ok(Self::wrap(Self::raw_dup(handle)?))
+    }
+
+    /// Duplicate `handle` into a new, independently-closable handle via
+    /// `DuplicateHandle(GetCurrentProcess(), handle, GetCurrentProcess(), &out, 0, FALSE, DUPLICATE_SAME_ACCESS)`.
+    fn raw_dup(handle: RawHandle) -> io::Result<RawHandle> {
+        let mut new_handle: RawHandle = std::ptr::null_mut();
+        let current_process = unsafe { GetCurrentProcess() };
+        let ok = unsafe {
+            DuplicateHandle(
+                current_process,
+                handle,
+                current_process,
+                &mut new_handle,
+                0,
+                0,
+                DUPLICATE_SAME_ACCESS,
+            )
+        };
+        if ok == 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(new_handle)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for AutoClosingFD {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { CloseHandle(self.0) };
+            self.0 = std::ptr::null_mut();
+        }
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for AutoClosingFD {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.0
+    }
+}
+
+/// Implements `Clone` trait that calls `DuplicateHandle` on the underlying handle and returns a
+/// new instance wrapping the duplicated handle.
+///
+/// **Warning**: `clone()` will panic if `DuplicateHandle` returns an error.
+///
+/// Each `clone()`/`dup()` of `DuplicatingFD` contains a different handle.
+#[cfg(windows)]
+pub struct DuplicatingFD(AutoClosingFD);
+
+#[cfg(windows)]
+impl ManagedFD for DuplicatingFD {
+    fn wrap(handle: RawHandle) -> Self {
+        DuplicatingFD(AutoClosingFD::wrap(handle))
+    }
+
+    fn dup_wrap(handle: RawHandle) -> io::Result<Self> {
+        Ok(DuplicatingFD(AutoClosingFD::dup_wrap(handle)?))
+    }
+
+    fn dup(&self) -> io::Result<Self> {
+        Ok(DuplicatingFD(AutoClosingFD::wrap(AutoClosingFD::raw_dup(
+            self.as_raw_handle(),
+        )?)))
+    }
+}
+
+#[cfg(windows)]
+impl Clone for DuplicatingFD {
+    fn clone(&self) -> Self {
+        self.dup().unwrap()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for DuplicatingFD {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.0.as_raw_handle()
+    }
+}
+
+/// A Windows `SOCKET` is a handle-sized value like `HANDLE`, so expose it the same way for
+/// callers that need `AsRawSocket`. Note that `Drop` always closes via `CloseHandle`, which
+/// Microsoft documents as valid for socket handles, though `closesocket` is the canonical winsock
+/// API - prefer wrapping sockets with a type that calls `closesocket` if you need that guarantee.
+#[cfg(windows)]
+impl AsRawSocket for DuplicatingFD {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.as_raw_handle() as RawSocket
+    }
+}
+
+/// Implements `Clone` trait that creates new `SharedFD` with `Arc::clone` of the
+/// embedded handle.
+///
+/// Each `clone()`/`dup()` of `SharedFD` contains the same handle.
+#[cfg(windows)]
+pub struct SharedFD(Arc<AutoClosingFD>);
+
+#[cfg(windows)]
+impl ManagedFD for SharedFD {
+    fn wrap(handle: RawHandle) -> Self {
+        SharedFD(Arc::new(AutoClosingFD::wrap(handle)))
+    }
+
+    fn dup_wrap(handle: RawHandle) -> io::Result<Self> {
+        Ok(SharedFD(Arc::new(AutoClosingFD::dup_wrap(handle)?)))
+    }
+
+    fn dup(&self) -> io::Result<Self> {
+        Ok(SharedFD(self.0.clone()))
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for SharedFD {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.0.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for SharedFD {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.as_raw_handle() as RawSocket
+    }
+}
+
+#[cfg(windows)]
 impl Clone for SharedFD {
     fn clone(&self) -> Self {
         self.dup().unwrap()